@@ -0,0 +1,303 @@
+//! Real-prover (KZG and IPA) benchmarks for `EvmCircuit`.
+//!
+//! `evm_circuit::test::run_test_circuit` only ever drives `MockProver`, which
+//! is fast but reports nothing about setup/proving/verifying cost or proof
+//! size. This harness runs the actual `TestCircuit` through a full
+//! `keygen_pk` -> `create_proof` -> `verify_proof` round trip for both
+//! commitment schemes and reports timings and proof size, so opcode-mix and
+//! `get_num_rows_required` regressions show up before they hit CI's
+//! slower, gated real-prover pass.
+//!
+//! The `(degree, block_size)` table driving the sweep is read from
+//! `benches/Config.toml`.
+
+use bus_mapping::mock::BlockData;
+use criterion::{criterion_group, criterion_main, Criterion};
+use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, Word};
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{create_proof, keygen_pk, keygen_vk, verify_proof},
+    poly::{
+        commitment::ParamsProver,
+        ipa::{
+            commitment::{IPACommitmentScheme, ParamsIPA},
+            multiopen::{ProverIPA, VerifierIPA},
+            strategy::SingleStrategy as IpaSingleStrategy,
+        },
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{ProverSHPLONK, VerifierSHPLONK},
+            strategy::SingleStrategy as KzgSingleStrategy,
+        },
+    },
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer},
+};
+use mock::TestContext;
+use rand::rngs::OsRng;
+use serde::Deserialize;
+use std::time::Instant;
+use strum::IntoEnumIterator;
+use zkevm_circuits::evm_circuit::{
+    table::FixedTableTag,
+    test::TestCircuit,
+    witness::{block_convert, Block},
+};
+
+/// Build a block that actually executes `block_size` `PUSH1`/`POP` pairs
+/// (`2 * block_size` opcode steps, then a final `STOP`): `STOP` halts on the
+/// first occurrence, so the pairs are used instead of repeated `STOP`s, keeping the benchmarked proof a function of `block_size`
+/// rather than always being a single-step trace.
+fn dummy_block(block_size: usize) -> Block<Fr> {
+    let mut code = bytecode! {};
+    for _ in 0..block_size {
+        code.push(1, Word::zero());
+        code.write_op(OpcodeId::POP);
+    }
+    code.write_op(OpcodeId::STOP);
+
+    let test_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+    let block_data = BlockData::new_from_geth_data(GethData::from(test_ctx));
+    let mut builder = block_data.new_circuit_input_builder();
+    builder
+        .handle_block(&block_data.eth_block, &block_data.geth_traces)
+        .unwrap();
+    block_convert(&builder.block, &builder.code_db)
+}
+
+#[derive(Debug, Deserialize)]
+struct CircuitBenchConfigFile {
+    config: Vec<CircuitBenchConfig>,
+}
+
+/// One row of `Config.toml`: the block size to benchmark, and optionally the
+/// circuit degree to use. When `degree` is omitted, it's derived from the
+/// built block and fixed table by `degree_for_block`.
+#[derive(Debug, Deserialize)]
+struct CircuitBenchConfig {
+    block_size: usize,
+    #[serde(default)]
+    degree: Option<u32>,
+}
+
+impl CircuitBenchConfig {
+    /// Degree large enough to fit `block`'s required step rows, the full
+    /// fixed table, and its bytecode — the same `log2_ceil` maxes
+    /// `run_test_circuit_with_floor_planner` (`evm_circuit.rs`) derives
+    /// from `get_num_rows_required` and the fixed-table-tag/bytecode-length
+    /// counts, reused here rather than re-estimated from `block_size`
+    /// alone (which ignores the fixed table entirely).
+    fn degree_for_block(block: &Block<Fr>, fixed_table_tags: &[FixedTableTag]) -> u32 {
+        let log2_ceil = |n: usize| usize::BITS - n.leading_zeros() - (n & (n - 1) == 0) as u32;
+
+        let k = log2_ceil(
+            64 + fixed_table_tags
+                .iter()
+                .map(|tag| tag.build::<Fr>().count())
+                .sum::<usize>(),
+        );
+        let k = k.max(log2_ceil(
+            64 + block
+                .bytecodes
+                .values()
+                .map(|bytecode| bytecode.bytes.len())
+                .sum::<usize>(),
+        ));
+        k.max(log2_ceil(64 + TestCircuit::get_num_rows_required(block)))
+    }
+
+    fn degree(&self, block: &Block<Fr>, fixed_table_tags: &[FixedTableTag]) -> u32 {
+        self.degree
+            .unwrap_or_else(|| Self::degree_for_block(block, fixed_table_tags))
+    }
+}
+
+fn load_bench_configs() -> Vec<CircuitBenchConfig> {
+    let raw = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/benches/Config.toml"))
+        .expect("benches/Config.toml should be present");
+    toml::from_str::<CircuitBenchConfigFile>(&raw)
+        .expect("benches/Config.toml should be well-formed")
+        .config
+}
+
+/// Prove/verify timings and proof size for a single `(degree, block_size)`
+/// point, reported by both commitment schemes. `setup` (trusted setup +
+/// `keygen_vk`/`keygen_pk`) is measured once per config, outside the
+/// sampled `create_proof`/`verify_proof` loop, since it's a one-time cost
+/// in practice and not what the steady-state prover/verifier benchmark
+/// should be dominated by.
+#[derive(Debug)]
+struct ProverBenchResult {
+    scheme: &'static str,
+    degree: u32,
+    block_size: usize,
+    setup: std::time::Duration,
+    prove: std::time::Duration,
+    verify: std::time::Duration,
+    proof_size: usize,
+}
+
+struct KzgSetup {
+    general_params: ParamsKZG<Bn256>,
+    verifier_params: ParamsKZG<Bn256>,
+    pk: halo2_proofs::plonk::ProvingKey<G1Affine>,
+}
+
+fn setup_kzg(circuit: &TestCircuit<Fr>, degree: u32) -> (KzgSetup, std::time::Duration) {
+    let setup_started = Instant::now();
+    let general_params = ParamsKZG::<Bn256>::setup(degree, OsRng);
+    let verifier_params = general_params.verifier_params().clone();
+    let vk = keygen_vk(&general_params, circuit).expect("keygen_vk should not fail");
+    let pk = keygen_pk(&general_params, vk, circuit).expect("keygen_pk should not fail");
+    let setup = setup_started.elapsed();
+
+    (
+        KzgSetup {
+            general_params,
+            verifier_params,
+            pk,
+        },
+        setup,
+    )
+}
+
+fn prove_and_verify_kzg(
+    circuit: &TestCircuit<Fr>,
+    setup: &KzgSetup,
+) -> (std::time::Duration, std::time::Duration, usize) {
+    let prove_started = Instant::now();
+    let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<_>, _, _, _, _>(
+        &setup.general_params,
+        &setup.pk,
+        std::slice::from_ref(circuit),
+        &[&[]],
+        OsRng,
+        &mut transcript,
+    )
+    .expect("create_proof should not fail");
+    let proof = transcript.finalize();
+    let prove = prove_started.elapsed();
+
+    let verify_started = Instant::now();
+    let mut verifier_transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(&proof[..]);
+    let strategy = KzgSingleStrategy::new(&setup.verifier_params);
+    verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<_>, _, _, _>(
+        &setup.verifier_params,
+        setup.pk.get_vk(),
+        strategy,
+        &[&[]],
+        &mut verifier_transcript,
+    )
+    .expect("verify_proof should not fail");
+    let verify = verify_started.elapsed();
+
+    (prove, verify, proof.len())
+}
+
+struct IpaSetup {
+    params: ParamsIPA<G1Affine>,
+    pk: halo2_proofs::plonk::ProvingKey<G1Affine>,
+}
+
+fn setup_ipa(circuit: &TestCircuit<Fr>, degree: u32) -> (IpaSetup, std::time::Duration) {
+    let setup_started = Instant::now();
+    let params = ParamsIPA::<G1Affine>::new(degree);
+    let vk = keygen_vk(&params, circuit).expect("keygen_vk should not fail");
+    let pk = keygen_pk(&params, vk, circuit).expect("keygen_pk should not fail");
+    let setup = setup_started.elapsed();
+
+    (IpaSetup { params, pk }, setup)
+}
+
+fn prove_and_verify_ipa(
+    circuit: &TestCircuit<Fr>,
+    setup: &IpaSetup,
+) -> (std::time::Duration, std::time::Duration, usize) {
+    let prove_started = Instant::now();
+    let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+    create_proof::<IPACommitmentScheme<G1Affine>, ProverIPA<_>, _, _, _, _>(
+        &setup.params,
+        &setup.pk,
+        std::slice::from_ref(circuit),
+        &[&[]],
+        OsRng,
+        &mut transcript,
+    )
+    .expect("create_proof should not fail");
+    let proof = transcript.finalize();
+    let prove = prove_started.elapsed();
+
+    let verify_started = Instant::now();
+    let mut verifier_transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(&proof[..]);
+    let strategy = IpaSingleStrategy::new(&setup.params);
+    verify_proof::<IPACommitmentScheme<G1Affine>, VerifierIPA<_>, _, _, _>(
+        &setup.params,
+        setup.pk.get_vk(),
+        strategy,
+        &[&[]],
+        &mut verifier_transcript,
+    )
+    .expect("verify_proof should not fail");
+    let verify = verify_started.elapsed();
+
+    (prove, verify, proof.len())
+}
+
+fn report(result: &ProverBenchResult) {
+    println!(
+        "evm_circuit/{} k={} block_size={}: setup {:?}, prove {:?}, verify {:?}, proof size {} bytes",
+        result.scheme, result.degree, result.block_size, result.setup, result.prove, result.verify, result.proof_size,
+    );
+}
+
+fn evm_circuit_real_prover(c: &mut Criterion) {
+    for cfg in load_bench_configs() {
+        let fixed_table_tags: Vec<FixedTableTag> = FixedTableTag::iter().collect();
+        let block = dummy_block(cfg.block_size);
+        let degree = cfg.degree(&block, &fixed_table_tags);
+        let circuit = TestCircuit::<Fr>::new(block, fixed_table_tags);
+
+        let (kzg_setup, kzg_setup_time) = setup_kzg(&circuit, degree);
+        let (kzg_prove, kzg_verify, kzg_proof_size) = prove_and_verify_kzg(&circuit, &kzg_setup);
+        report(&ProverBenchResult {
+            scheme: "kzg",
+            degree,
+            block_size: cfg.block_size,
+            setup: kzg_setup_time,
+            prove: kzg_prove,
+            verify: kzg_verify,
+            proof_size: kzg_proof_size,
+        });
+
+        let (ipa_setup, ipa_setup_time) = setup_ipa(&circuit, degree);
+        let (ipa_prove, ipa_verify, ipa_proof_size) = prove_and_verify_ipa(&circuit, &ipa_setup);
+        report(&ProverBenchResult {
+            scheme: "ipa",
+            degree,
+            block_size: cfg.block_size,
+            setup: ipa_setup_time,
+            prove: ipa_prove,
+            verify: ipa_verify,
+            proof_size: ipa_proof_size,
+        });
+
+        // Only `create_proof`/`verify_proof` are sampled here: `kzg_setup`/
+        // `ipa_setup` already paid the one-time trusted-setup + keygen cost
+        // above.
+        c.bench_function(
+            &format!("evm_circuit kzg k={} block_size={}", degree, cfg.block_size),
+            |b| b.iter(|| prove_and_verify_kzg(&circuit, &kzg_setup)),
+        );
+        c.bench_function(
+            &format!("evm_circuit ipa k={} block_size={}", degree, cfg.block_size),
+            |b| b.iter(|| prove_and_verify_ipa(&circuit, &ipa_setup)),
+        );
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = evm_circuit_real_prover
+}
+criterion_main!(benches);
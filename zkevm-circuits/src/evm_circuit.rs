@@ -14,6 +14,7 @@ pub mod witness;
 use crate::table::{BytecodeTable, TxTable};
 use eth_types::Field;
 use execution::ExecutionConfig;
+use halo2_proofs::{dev::CircuitCost, halo2curves::group::prime::PrimeGroup};
 use itertools::Itertools;
 use table::{FixedTableTag, LookupTable};
 use witness::Block;
@@ -138,6 +139,33 @@ impl<F: Field> EvmCircuit<F> {
         }
         num_rows
     }
+
+    /// Estimate the proof size, number of polynomial commitments, and
+    /// marginal per-row proving cost of `circuit` at degree `k`, using
+    /// halo2's `CircuitCost` model. This predicts the cost impact of a new
+    /// execution gadget without running a full proof.
+    ///
+    /// `CircuitCost::measure` itself just needs a `Circuit` impl — it
+    /// re-synthesizes `circuit` to discover row usage, so it can't take
+    /// `self`'s active rows as an input. What `self`/`block` add on top is
+    /// a sanity check: `k` must be large enough to fit the rows `block`
+    /// actually needs, per `get_num_rows_required`, or this panics instead
+    /// of silently truncating the circuit's witness.
+    pub fn get_circuit_cost<G, C>(&self, block: &Block<F>, k: u32, circuit: &C) -> CircuitCost<G, C>
+    where
+        G: PrimeGroup<Scalar = F>,
+        C: Circuit<F>,
+    {
+        let required_rows = self.get_num_rows_required(block);
+        assert!(
+            (1usize << k) >= required_rows,
+            "degree k={} (2^k={}) is too small to fit the {} rows `block` requires",
+            k,
+            1usize << k,
+            required_rows,
+        );
+        CircuitCost::measure(k, circuit)
+    }
 }
 
 #[cfg(any(feature = "test", test))]
@@ -148,16 +176,18 @@ pub mod test {
         table::{load_block, load_bytecodes, load_rws, load_txs, BlockTable, RwTable},
         util::power_of_randomness_from_instance,
     };
+    use super::step::ExecutionState;
     use eth_types::{Field, Word};
     use halo2_proofs::{
-        circuit::{Layouter, SimpleFloorPlanner},
-        dev::{MockProver, VerifyFailure},
+        circuit::{FloorPlanner, Layouter, SimpleFloorPlanner},
+        dev::{CircuitGates, MockProver, TracingFloorPlanner, VerifyFailure},
         plonk::{Circuit, ConstraintSystem, Error},
     };
     use rand::{
         distributions::uniform::{SampleRange, SampleUniform},
         random, thread_rng, Rng,
     };
+    use std::{collections::HashMap, marker::PhantomData};
     use strum::IntoEnumIterator;
 
     pub(crate) fn rand_range<T, R>(range: R) -> T
@@ -189,24 +219,40 @@ pub mod test {
         evm_circuit: EvmCircuit<F>,
     }
 
-    #[derive(Default)]
-    pub struct TestCircuit<F> {
+    /// `FP` selects the `FloorPlanner` used to synthesize the circuit.
+    /// Defaults to `SimpleFloorPlanner`; pass `TracingFloorPlanner<P>` (see
+    /// [`run_test_circuit_traced`]) to record region/assignment bookkeeping
+    /// and catch double-assigned or out-of-region cells that `MockProver`
+    /// otherwise tolerates silently.
+    pub struct TestCircuit<F, FP = SimpleFloorPlanner> {
         block: Block<F>,
         fixed_table_tags: Vec<FixedTableTag>,
+        _marker: PhantomData<FP>,
     }
 
-    impl<F> TestCircuit<F> {
+    impl<F: Default, FP> Default for TestCircuit<F, FP> {
+        fn default() -> Self {
+            Self {
+                block: Block::default(),
+                fixed_table_tags: Vec::default(),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl<F, FP> TestCircuit<F, FP> {
         pub fn new(block: Block<F>, fixed_table_tags: Vec<FixedTableTag>) -> Self {
             Self {
                 block,
                 fixed_table_tags,
+                _marker: PhantomData,
             }
         }
     }
 
-    impl<F: Field> Circuit<F> for TestCircuit<F> {
+    impl<F: Field, FP: FloorPlanner> Circuit<F> for TestCircuit<F, FP> {
         type Config = TestCircuitConfig<F>;
-        type FloorPlanner = SimpleFloorPlanner;
+        type FloorPlanner = FP;
 
         fn without_witnesses(&self) -> Self {
             Self::default()
@@ -288,9 +334,21 @@ pub mod test {
             let config = TestCircuit::configure(&mut cs);
             config.evm_circuit.get_active_rows(block)
         }
+
+        /// Estimate proof size and commitment cost of proving `self` at
+        /// degree `k`, without running a prover. Panics if `k` is too
+        /// small to fit `self.block`'s required rows.
+        pub fn get_circuit_cost<G: PrimeGroup<Scalar = F>>(
+            &self,
+            k: u32,
+        ) -> CircuitCost<G, TestCircuit<F>> {
+            let mut cs = ConstraintSystem::default();
+            let config = TestCircuit::configure(&mut cs);
+            config.evm_circuit.get_circuit_cost(&self.block, k, self)
+        }
     }
 
-    pub fn run_test_circuit<F: Field>(
+    fn run_test_circuit_with_floor_planner<F: Field, FP: FloorPlanner>(
         block: Block<F>,
         fixed_table_tags: Vec<FixedTableTag>,
     ) -> Result<(), Vec<VerifyFailure>> {
@@ -318,11 +376,202 @@ pub mod test {
             .map(|exp| vec![block.randomness.pow(&[exp, 0, 0, 0]); (1 << k) - 64])
             .collect();
         let (active_gate_rows, active_lookup_rows) = TestCircuit::get_active_rows(&block);
-        let circuit = TestCircuit::<F>::new(block, fixed_table_tags);
+        let circuit = TestCircuit::<F, FP>::new(block, fixed_table_tags);
         let prover = MockProver::<F>::run(k, &circuit, power_of_randomness).unwrap();
         prover.verify_at_rows(active_gate_rows.into_iter(), active_lookup_rows.into_iter())
     }
 
+    pub fn run_test_circuit<F: Field>(
+        block: Block<F>,
+        fixed_table_tags: Vec<FixedTableTag>,
+    ) -> Result<(), Vec<VerifyFailure>> {
+        run_test_circuit_with_floor_planner::<F, SimpleFloorPlanner>(block, fixed_table_tags)
+    }
+
+    /// A witness-assignment anomaly surfaced while synthesizing under
+    /// `TracingFloorPlanner`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum TracingDiagnostic {
+        /// The same cell was assigned more than once.
+        DoubleAssignedCell { column: String, row: usize },
+        /// A cell was assigned while no region was active.
+        OutOfRegionAssignment { column: String, row: usize },
+    }
+
+    #[derive(Default)]
+    struct RegionTrackerState {
+        next_span_id: u64,
+        /// Spans created by `new_span`, keyed by id, recording only whether
+        /// each is a `"region"` span — `enter`/`exit` only get the `Id`, not
+        /// the span's metadata, so this has to be looked up.
+        region_spans: std::collections::HashSet<u64>,
+        region_depth: usize,
+        seen_cells: std::collections::HashSet<(String, usize)>,
+        diagnostics: Vec<TracingDiagnostic>,
+        /// Whether a `"region"`-named span has ever been entered. Cleared
+        /// is fine as `false`; used by [`run_test_circuit_traced`] to tell
+        /// "the circuit has no regions" apart from "this subscriber's
+        /// assumed span name doesn't match what `TracingFloorPlanner`
+        /// actually emits", which would otherwise both look like an empty
+        /// diagnostics list.
+        saw_region_span: bool,
+        /// Whether a cell-assignment event has ever been observed, by the
+        /// same reasoning as `saw_region_span`.
+        saw_cell_assignment: bool,
+    }
+
+    /// A `tracing::Subscriber` that listens to the region-enter/exit spans
+    /// and cell-assignment events `TracingFloorPlanner` emits, flagging
+    /// cells assigned more than once and assignments made while no region
+    /// is active.
+    ///
+    /// The exact span/event/field names this relies on (`"region"` spans;
+    /// events carrying `column`/`row` fields) are `TracingFloorPlanner`'s
+    /// instrumentation surface in `halo2_proofs`, which this crate depends
+    /// on via `branch = "main"` rather than a pinned `rev` (see
+    /// `Cargo.toml`), so that surface can drift out from under this file
+    /// without a version bump to notice. Cell-assignment events are
+    /// therefore matched by the presence of `column`+`row` fields rather
+    /// than by event name/message, since those are load-bearing for this
+    /// subscriber's job and stable across how the event happens to be
+    /// phrased; `saw_region_span`/`saw_cell_assignment` give
+    /// [`run_test_circuit_traced`] a way to detect if even that weaker
+    /// assumption stops holding.
+    struct RegionTracker {
+        state: std::sync::Arc<std::sync::Mutex<RegionTrackerState>>,
+    }
+
+    #[derive(Default)]
+    struct CellAssignmentVisitor {
+        column: Option<String>,
+        row: Option<usize>,
+    }
+
+    impl tracing::field::Visit for CellAssignmentVisitor {
+        fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+            if field.name() == "row" {
+                self.row = Some(value as usize);
+            }
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            match field.name() {
+                "column" => self.column = Some(format!("{:?}", value)),
+                "row" => self.row = self.row.or_else(|| format!("{:?}", value).parse().ok()),
+                _ => {}
+            }
+        }
+    }
+
+    impl tracing::Subscriber for RegionTracker {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut state = self.state.lock().unwrap();
+            let id = state.next_span_id;
+            state.next_span_id += 1;
+            if span.metadata().name() == "region" {
+                state.region_spans.insert(id);
+            }
+            tracing::span::Id::from_u64(id + 1)
+        }
+
+        fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut visitor = CellAssignmentVisitor::default();
+            event.record(&mut visitor);
+            let (Some(column), Some(row)) = (visitor.column, visitor.row) else {
+                return;
+            };
+
+            let mut state = self.state.lock().unwrap();
+            state.saw_cell_assignment = true;
+            if state.region_depth == 0 {
+                state.diagnostics.push(TracingDiagnostic::OutOfRegionAssignment {
+                    column: column.clone(),
+                    row,
+                });
+            }
+            if !state.seen_cells.insert((column.clone(), row)) {
+                state
+                    .diagnostics
+                    .push(TracingDiagnostic::DoubleAssignedCell { column, row });
+            }
+        }
+
+        // `region_depth` tracks spans that are *entered*, not merely
+        // created: `new_span` only registers that an id is a region, since a
+        // span can be created ahead of being entered (or re-entered), and
+        // counting depth there rather than here would desync from the
+        // actual region nesting that's active when `event` fires.
+        fn enter(&self, span: &tracing::span::Id) {
+            let mut state = self.state.lock().unwrap();
+            if state.region_spans.contains(&(span.into_u64() - 1)) {
+                state.region_depth += 1;
+                state.saw_region_span = true;
+            }
+        }
+
+        fn exit(&self, span: &tracing::span::Id) {
+            let mut state = self.state.lock().unwrap();
+            if state.region_spans.contains(&(span.into_u64() - 1)) {
+                state.region_depth = state.region_depth.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Like [`run_test_circuit`], but synthesizes `TestCircuit` under
+    /// `TracingFloorPlanner`, which records every region start, selector
+    /// enable, and cell assignment. A [`RegionTracker`] subscriber is
+    /// installed for the duration of synthesis and verification. If
+    /// verification passes, its collected [`TracingDiagnostic`]s are
+    /// returned as `Ok`; if it fails, the `VerifyFailure`s are returned as
+    /// `Err` and the diagnostics are dropped. The diagnostics surface
+    /// double-assigned cells and cells assigned while no region is active —
+    /// witness-assignment bugs in opcode gadgets that `MockProver` under
+    /// `SimpleFloorPlanner` silently tolerates. An assignment made inside
+    /// one region to rows outside that region's extent is not detected.
+    ///
+    /// After verification passes, panics if `RegionTracker` never saw a
+    /// `"region"` span, or never saw a cell-assignment event: every real
+    /// circuit assigns cells inside regions, so either case means the
+    /// assumed `TracingFloorPlanner` instrumentation surface (see
+    /// `RegionTracker`'s doc comment) no longer matches reality, and an
+    /// empty `Vec<TracingDiagnostic>` in that case would be mistaken for
+    /// "no bugs" rather than "this tool stopped working".
+    pub fn run_test_circuit_traced<F: Field>(
+        block: Block<F>,
+        fixed_table_tags: Vec<FixedTableTag>,
+    ) -> Result<Vec<TracingDiagnostic>, Vec<VerifyFailure>> {
+        let state = std::sync::Arc::new(std::sync::Mutex::new(RegionTrackerState::default()));
+        let tracker = RegionTracker {
+            state: state.clone(),
+        };
+
+        let result = tracing::subscriber::with_default(tracker, || {
+            run_test_circuit_with_floor_planner::<F, TracingFloorPlanner<SimpleFloorPlanner>>(
+                block,
+                fixed_table_tags,
+            )
+        });
+
+        result.map(|()| {
+            let state = state.lock().unwrap();
+            assert!(
+                state.saw_region_span && state.saw_cell_assignment,
+                "RegionTracker observed no region spans or cell-assignment events; \
+                 TracingFloorPlanner's instrumentation surface no longer matches what \
+                 RegionTracker assumes, so its diagnostics can't be trusted",
+            );
+            state.diagnostics.clone()
+        })
+    }
+
     pub fn run_test_circuit_incomplete_fixed_table<F: Field>(
         block: Block<F>,
     ) -> Result<(), Vec<VerifyFailure>> {
@@ -349,4 +598,176 @@ pub mod test {
     ) -> Result<(), Vec<VerifyFailure>> {
         run_test_circuit(block, FixedTableTag::iter().collect())
     }
+
+    /// Render `block`'s floor-planned regions and columns (the
+    /// `ExecutionConfig` advice/fixed columns plus `fixed_table` and
+    /// `byte_table`) to `path` as an SVG, and return the circuit's DOT
+    /// graph, so layout overlaps across execution states can be spotted
+    /// visually.
+    #[cfg(feature = "dev-graph")]
+    pub fn render_circuit_layout<F: Field>(
+        block: Block<F>,
+        fixed_table_tags: Vec<FixedTableTag>,
+        k: u32,
+        path: &str,
+    ) -> String {
+        use halo2_proofs::dev::{circuit_dot_graph, CircuitLayout};
+        use plotters::prelude::*;
+
+        let circuit = TestCircuit::new(block, fixed_table_tags);
+
+        let root = SVGBackend::new(path, (1024, 768)).into_drawing_area();
+        root.fill(&WHITE).expect("drawing area should be fillable");
+        let root = root
+            .titled("EVM Circuit Layout", ("sans-serif", 20))
+            .expect("drawing area should accept a title");
+        CircuitLayout::default()
+            .show_labels(true)
+            .render(k, &circuit, &root)
+            .expect("circuit layout should render");
+
+        circuit_dot_graph(&circuit)
+    }
+
+    /// Maximum constraint degree and cell-query count contributed by the
+    /// polynomial gates of a single `ExecutionState`'s gadget.
+    ///
+    /// `CircuitGates::collect` only enumerates `ConstraintSystem::gates`; it
+    /// has no visibility into lookup arguments (`ConstraintSystem::lookups`),
+    /// so `num_cell_queries` counts cell queries made by gate polynomials,
+    /// not how many lookups a gadget enables.
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct ExecutionStateGateCost {
+        pub num_gates: usize,
+        pub max_degree: usize,
+        pub num_cell_queries: usize,
+    }
+
+    /// Whether `gate_name` was registered by the gadget for `ExecutionState`
+    /// `name_prefix` (its `Debug` name), rather than merely starting with it.
+    /// Plain `starts_with` over-matches: `Debug` names like `ADD`/`ADDMOD`,
+    /// `MUL`/`MULMOD`, `CALL`/`CALLCODE`/`CALLDATASIZE`/`CALLDATACOPY`/
+    /// `CALLVALUE`, `CREATE`/`CREATE2`, `JUMP`/`JUMPI`/`JUMPDEST`, and
+    /// `RETURN`/`RETURNDATACOPY`/`RETURNDATASIZE` are literal prefixes of one
+    /// another, so a gate named e.g. `"ADDMOD ..."` would also count toward
+    /// `ADD`. Requiring the prefix to end at an identifier boundary (nothing
+    /// left, or the next byte isn't alphanumeric/`_`) rules that out.
+    fn gate_name_matches_state(gate_name: &str, name_prefix: &str) -> bool {
+        gate_name.strip_prefix(name_prefix).map_or(false, |rest| {
+            !rest.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+        })
+    }
+
+    /// Run `CircuitGates::collect` on the configured `TestCircuit` and
+    /// aggregate the result by the `ExecutionState` that registered each
+    /// gate, so it's possible to tell which opcode gadget pushes up the
+    /// circuit's maximum constraint degree (set by the worst gate) and how
+    /// many cells its gate polynomials query.
+    /// This does not account for lookup arguments; `CircuitGates` has no
+    /// visibility into those.
+    pub fn get_gate_costs_by_execution_state<F: Field>(
+    ) -> HashMap<ExecutionState, ExecutionStateGateCost> {
+        let gates = CircuitGates::collect::<F, TestCircuit<F>>();
+
+        ExecutionState::iter()
+            .filter_map(|state| {
+                let name_prefix = format!("{:?}", state);
+                let state_gates = gates
+                    .gates()
+                    .iter()
+                    .filter(|gate| gate_name_matches_state(gate.name(), &name_prefix));
+
+                let cost = state_gates.fold(ExecutionStateGateCost::default(), |mut cost, gate| {
+                    cost.num_gates += 1;
+                    cost.max_degree = cost.max_degree.max(gate.degree());
+                    cost.num_cell_queries += gate.queried_cells().len();
+                    cost
+                });
+
+                (cost.num_gates > 0).then(|| (state, cost))
+            })
+            .collect()
+    }
+
+    #[cfg(test)]
+    mod region_tracker_tests {
+        use super::*;
+
+        /// Runs `f` under a fresh `RegionTracker` and returns what it
+        /// collected. This exercises `RegionTracker`'s own bucketing/depth
+        /// logic against synthetic spans/events matching its assumed
+        /// contract (a `"region"`-named span; events carrying `column`/
+        /// `row` fields) — not against a live `TracingFloorPlanner` run,
+        /// since this tree has no way to verify that its actual
+        /// instrumentation matches (see `RegionTracker`'s doc comment).
+        fn run_tracked(f: impl FnOnce()) -> Vec<TracingDiagnostic> {
+            let state = std::sync::Arc::new(std::sync::Mutex::new(RegionTrackerState::default()));
+            let tracker = RegionTracker {
+                state: state.clone(),
+            };
+            tracing::subscriber::with_default(tracker, f);
+            state.lock().unwrap().diagnostics.clone()
+        }
+
+        #[test]
+        fn flags_double_assigned_cell_within_a_region() {
+            let diagnostics = run_tracked(|| {
+                let region = tracing::span!(tracing::Level::TRACE, "region");
+                let _enter = region.enter();
+                tracing::event!(tracing::Level::TRACE, column = "A", row = 0u64);
+                tracing::event!(tracing::Level::TRACE, column = "A", row = 0u64);
+            });
+
+            assert_eq!(
+                diagnostics,
+                vec![TracingDiagnostic::DoubleAssignedCell {
+                    column: "\"A\"".to_string(),
+                    row: 0,
+                }],
+            );
+        }
+
+        #[test]
+        fn flags_out_of_region_assignment() {
+            let diagnostics = run_tracked(|| {
+                tracing::event!(tracing::Level::TRACE, column = "A", row = 0u64);
+            });
+
+            assert_eq!(
+                diagnostics,
+                vec![TracingDiagnostic::OutOfRegionAssignment {
+                    column: "\"A\"".to_string(),
+                    row: 0,
+                }],
+            );
+        }
+
+        #[test]
+        fn reentering_the_same_region_span_does_not_desync_depth() {
+            let diagnostics = run_tracked(|| {
+                let region = tracing::span!(tracing::Level::TRACE, "region");
+                {
+                    let _enter = region.enter();
+                    tracing::event!(tracing::Level::TRACE, column = "A", row = 0u64);
+                }
+                // Re-entering the same span (rather than creating a new one)
+                // must not leave `region_depth` desynced afterward — an
+                // increment-in-`new_span` implementation gets this wrong,
+                // since the span is only ever created once.
+                {
+                    let _enter = region.enter();
+                    tracing::event!(tracing::Level::TRACE, column = "B", row = 0u64);
+                }
+                tracing::event!(tracing::Level::TRACE, column = "C", row = 0u64);
+            });
+
+            assert_eq!(
+                diagnostics,
+                vec![TracingDiagnostic::OutOfRegionAssignment {
+                    column: "\"C\"".to_string(),
+                    row: 0,
+                }],
+            );
+        }
+    }
 }